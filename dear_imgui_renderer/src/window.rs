@@ -1,16 +1,73 @@
 use std::num::NonZeroU32;
 use std::time::{Instant, Duration};
+use std::cell::{Cell, RefCell};
+use std::os::raw::c_void;
+use std::ffi::CString;
+use std::collections::HashMap;
 
 use glutin_winit::DisplayBuilder;
-use winit::{window::{Window, CursorIcon, WindowBuilder}, event::{Event, VirtualKeyCode}, dpi::{PhysicalSize, LogicalSize, Pixel, PhysicalPosition, LogicalPosition}, event_loop::{EventLoopWindowTarget, ControlFlow}};
+use winit::{window::{Window, WindowId, CursorIcon, WindowBuilder}, event::{Event, VirtualKeyCode}, dpi::{PhysicalSize, LogicalSize, Pixel, PhysicalPosition, LogicalPosition}, event_loop::{EventLoopWindowTarget, ControlFlow}};
 use dear_imgui_sys::*;
 use dear_imgui as imgui;
-use glutin::{prelude::*, config::{Config, ConfigTemplateBuilder}, display::GetGlDisplay, surface::{SurfaceAttributesBuilder, WindowSurface, Surface}, context::{ContextAttributesBuilder, ContextApi, PossiblyCurrentContext}};
+use glutin::{prelude::*, config::{Config, ConfigSurfaceTypes, ConfigTemplateBuilder}, display::GetGlDisplay, surface::{SurfaceAttributesBuilder, WindowSurface, PbufferSurface, Surface}, context::{ContextAttributesBuilder, ContextApi, PossiblyCurrentContext}};
 use raw_window_handle::HasRawWindowHandle;
 use anyhow::{Result, anyhow};
+use glow::HasContext;
 use crate::renderer::{Renderer, Application};
 use crate::conv::{from_imgui_cursor, to_imgui_key, to_imgui_button};
 
+// The platform callbacks below are free functions (ImGui needs plain `extern "C" fn`s, not
+// closures), so the event loop target and the main window they need to create secondary OS
+// windows are smuggled through thread-local storage, set at the top of every `do_event_with_data`
+// call. It is not cleared afterwards; the callbacks that read it only run from inside that call.
+thread_local! {
+    static VIEWPORT_CONTEXT: Cell<(*const c_void, *const c_void)> = Cell::new((std::ptr::null(), std::ptr::null()));
+    // Backs `GetClipboardTextFn`: ImGui expects the returned pointer to stay valid at least
+    // until the next call, so the converted C string lives here rather than on the stack.
+    static CLIPBOARD_TEXT: RefCell<CString> = RefCell::new(CString::default());
+    // Maps each secondary viewport's winit `WindowId` back to its `ImGuiViewport`, so WindowEvents
+    // from those extra windows (registered/unregistered in the create/destroy callbacks) can be
+    // routed to the right viewport instead of being dropped.
+    static VIEWPORT_WINDOWS: RefCell<HashMap<WindowId, *mut ImGuiViewport>> = RefCell::new(HashMap::new());
+}
+
+/// A secondary OS window backing an ImGui platform viewport (a widget window dragged outside of
+/// the main window, when `ImGuiConfigFlags_ViewportsEnable` is set).
+struct ViewportWindow {
+    window: Window,
+    surface: Surface<WindowSurface>,
+    gl_context: PossiblyCurrentContext,
+}
+
+/// How winit's reported `scale_factor` is translated into the transform used for ImGui sizing,
+/// mirroring `imgui-winit-support`'s `HiDpiMode`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HiDpiMode {
+    /// Use winit's scale factor as-is.
+    Default,
+    /// Round winit's scale factor to the nearest integer, so fonts and borders stay crisp
+    /// instead of blurring on fractional-scale displays.
+    Rounded,
+    /// Ignore winit's scale factor and always use this one, e.g. for reproducible screenshots.
+    Locked(f64),
+}
+
+impl HiDpiMode {
+    fn apply(self, scale_factor: f64) -> f64 {
+        match self {
+            HiDpiMode::Default => scale_factor,
+            HiDpiMode::Rounded => scale_factor.round(),
+            HiDpiMode::Locked(factor) => factor,
+        }
+    }
+}
+
+impl Default for HiDpiMode {
+    fn default() -> HiDpiMode {
+        HiDpiMode::Default
+    }
+}
+
 struct MainWindowStatus {
     last_frame: Instant,
     last_input_time: Instant,
@@ -38,6 +95,13 @@ pub struct MainWindow {
     // The surface must be dropped before the window.
     surface: Surface<WindowSurface>,
     window: Window,
+    // Kept around so secondary viewport windows can be created against the same pixel format
+    // and share the main GL context.
+    gl_config: Config,
+    // Boxed so its address, registered as `io.ClipboardUserData`, stays stable even though
+    // `MainWindow` itself gets moved out of `new()`.
+    clipboard: Box<copypasta::ClipboardContext>,
+    hidpi_mode: Cell<HiDpiMode>,
 }
 
 pub struct MainWindowWithRenderer<A> {
@@ -47,6 +111,24 @@ pub struct MainWindowWithRenderer<A> {
     app: A,
 }
 
+unsafe extern "C" fn get_clipboard_text(user_data: *mut c_void) -> *const std::os::raw::c_char {
+    use copypasta::ClipboardProvider;
+    let clipboard = &mut *(user_data as *mut copypasta::ClipboardContext);
+    let text = clipboard.get_contents().unwrap_or_default();
+    let text = CString::new(text).unwrap_or_default();
+    CLIPBOARD_TEXT.with(|c| {
+        *c.borrow_mut() = text;
+        c.borrow().as_ptr()
+    })
+}
+
+unsafe extern "C" fn set_clipboard_text(user_data: *mut c_void, text: *const std::os::raw::c_char) {
+    use copypasta::ClipboardProvider;
+    let clipboard = &mut *(user_data as *mut copypasta::ClipboardContext);
+    let text = std::ffi::CStr::from_ptr(text).to_string_lossy().into_owned();
+    let _ = clipboard.set_contents(text);
+}
+
 impl MainWindow {
     pub fn new<EventUserType>(event_loop: &EventLoopWindowTarget<EventUserType>) -> Result<MainWindow> {
         let window_builder = WindowBuilder::new();
@@ -112,40 +194,367 @@ impl MainWindow {
         // Enable v-sync to avoid consuming too much CPU
         let _ = surface.set_swap_interval(&gl_context, glutin::surface::SwapInterval::Wait(NonZeroU32::new(1).unwrap()));
 
+        let clipboard = Box::new(copypasta::ClipboardContext::new().map_err(|e| anyhow!("{}", e))?);
+        unsafe {
+            let io = &mut *ImGui_GetIO();
+            io.ClipboardUserData = clipboard.as_ref() as *const copypasta::ClipboardContext as *mut c_void;
+            io.GetClipboardTextFn = Some(get_clipboard_text);
+            io.SetClipboardTextFn = Some(set_clipboard_text);
+        }
+
         Ok(MainWindow {
             gl_context,
             window,
             surface,
+            gl_config,
+            clipboard,
+            hidpi_mode: Cell::new(HiDpiMode::default()),
         })
     }
     pub fn gl_context(&self) -> &glutin::context::PossiblyCurrentContext {
         &self.gl_context
     }
 
+    /// The scale factor to use for logical↔physical conversions, after applying the
+    /// configured [`HiDpiMode`] to winit's own `scale_factor`.
+    pub fn scale_factor(&self) -> f64 {
+        self.hidpi_mode.get().apply(self.window.scale_factor())
+    }
+
     pub fn to_logical_size<X: Pixel, Y: Pixel>(&self, size: PhysicalSize<X>) -> LogicalSize<Y> {
-        let scale = self.window.scale_factor();
+        let scale = self.scale_factor();
         size.to_logical(scale)
     }
-    #[allow(dead_code)]
     pub fn to_physical_size<X: Pixel, Y: Pixel>(&self, size: LogicalSize<X>) -> PhysicalSize<Y> {
-        let scale = self.window.scale_factor();
+        let scale = self.scale_factor();
         size.to_physical(scale)
     }
     pub fn to_logical_pos<X: Pixel, Y: Pixel>(&self, pos: PhysicalPosition<X>) -> LogicalPosition<Y> {
-        let scale = self.window.scale_factor();
+        let scale = self.scale_factor();
         pos.to_logical(scale)
     }
-    #[allow(dead_code)]
     pub fn to_physical_pos<X: Pixel, Y: Pixel>(&self, pos: LogicalPosition<X>) -> PhysicalPosition<Y> {
-        let scale = self.window.scale_factor();
+        let scale = self.scale_factor();
         pos.to_physical(scale)
     }
 }
 
+/// A window-less offscreen GL target: a pbuffer surface plus an FBO instead of a default
+/// framebuffer. Lets widget tests and CI render and read pixels back without opening a window.
+/// The GL display is still created from a winit event loop, so a display connection is needed.
+pub struct HeadlessWindow {
+    gl_context: PossiblyCurrentContext,
+    surface: Surface<PbufferSurface>,
+    gl: glow::Context,
+    framebuffer: glow::NativeFramebuffer,
+    color_renderbuffer: glow::NativeRenderbuffer,
+    width: u32,
+    height: u32,
+}
+
+impl HeadlessWindow {
+    pub fn new<EventUserType>(event_loop: &EventLoopWindowTarget<EventUserType>, width: u32, height: u32, scale: f32) -> Result<HeadlessWindow> {
+        let template = ConfigTemplateBuilder::new()
+            .prefer_hardware_accelerated(Some(true))
+            .with_depth_size(0)
+            .with_stencil_size(0)
+            .with_surface_type(ConfigSurfaceTypes::PBUFFER);
+
+        let display_builder = DisplayBuilder::new();
+        let (_, gl_config) = display_builder
+            .build(event_loop, template, |configs| {
+                configs
+                    .reduce(|cfg1, cfg2| {
+                        let t = |c: &Config| (c.num_samples(), c.depth_size(), c.stencil_size());
+                        if t(&cfg2) < t(&cfg1) {
+                            cfg2
+                        } else {
+                            cfg1
+                        }
+                    })
+                    .unwrap()
+            })
+            .map_err(|e| anyhow!("{:#?}", e))?;
+
+        let gl_display = gl_config.display();
+        let context_attributes = ContextAttributesBuilder::new().build(None);
+        let fallback_context_attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::Gles(None))
+            .build(None);
+        let not_current_gl_context = unsafe {
+            gl_display
+                .create_context(&gl_config, &context_attributes)
+                .or_else(|_| gl_display.create_context(&gl_config, &fallback_context_attributes))?
+        };
+
+        let pbuffer_attrs = SurfaceAttributesBuilder::<PbufferSurface>::new().build(
+            NonZeroU32::new(width.max(1)).unwrap(),
+            NonZeroU32::new(height.max(1)).unwrap(),
+        );
+        let surface = unsafe { gl_display.create_pbuffer_surface(&gl_config, &pbuffer_attrs)? };
+        let gl_context = not_current_gl_context.make_current(&surface)?;
+
+        let gl = unsafe {
+            glow::Context::from_loader_function(|s| {
+                let s = std::ffi::CString::new(s).unwrap();
+                gl_display.get_proc_address(&s).cast()
+            })
+        };
+
+        let (framebuffer, color_renderbuffer) = unsafe {
+            let framebuffer = gl.create_framebuffer().map_err(|e| anyhow!("{e}"))?;
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+
+            let color_renderbuffer = gl.create_renderbuffer().map_err(|e| anyhow!("{e}"))?;
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(color_renderbuffer));
+            gl.renderbuffer_storage(glow::RENDERBUFFER, glow::RGBA8, width as i32, height as i32);
+            gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::RENDERBUFFER, Some(color_renderbuffer));
+
+            if gl.check_framebuffer_status(glow::FRAMEBUFFER) != glow::FRAMEBUFFER_COMPLETE {
+                return Err(anyhow!("headless framebuffer is incomplete"));
+            }
+            (framebuffer, color_renderbuffer)
+        };
+
+        unsafe {
+            let io = &mut *ImGui_GetIO();
+            io.DisplaySize = ImVec2 { x: width as f32 / scale, y: height as f32 / scale };
+            io.DisplayFramebufferScale = ImVec2 { x: scale, y: scale };
+            io.DeltaTime = 1.0 / 60.0;
+        }
+
+        Ok(HeadlessWindow {
+            gl_context,
+            surface,
+            gl,
+            framebuffer,
+            color_renderbuffer,
+            width,
+            height,
+        })
+    }
+
+    /// Renders one ImGui frame into the offscreen framebuffer.
+    pub fn do_frame<A: Application<Data=()>>(&mut self, renderer: &mut Renderer, app: &mut A) {
+        unsafe {
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+            self.gl.viewport(0, 0, self.width as i32, self.height as i32);
+            let io = &mut *ImGui_GetIO();
+            io.DeltaTime = 1.0 / 60.0;
+        }
+        static mut DUMMY: () = ();
+        renderer.do_frame(unsafe { &mut DUMMY }, app);
+    }
+
+    /// Reads the offscreen framebuffer back into a top-to-bottom RGBA image, for pixel-diff
+    /// tests or saving a screenshot.
+    pub fn read_pixels(&self) -> image::RgbaImage {
+        let mut pixels = vec![0u8; (self.width * self.height * 4) as usize];
+        unsafe {
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+            self.gl.read_pixels(
+                0, 0,
+                self.width as i32, self.height as i32,
+                glow::RGBA, glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(&mut pixels),
+            );
+        }
+        let mut image = image::RgbaImage::from_raw(self.width, self.height, pixels)
+            .expect("pixel buffer matches the image dimensions");
+        // glReadPixels returns rows bottom-to-top; flip to the usual top-to-bottom layout.
+        image::imageops::flip_vertical_in_place(&mut image);
+        image
+    }
+}
+
+impl Drop for HeadlessWindow {
+    fn drop(&mut self) {
+        // Another GL context (e.g. a secondary viewport's or another `HeadlessWindow`'s) may have
+        // been made current since this one was last used, and deleting GL objects against the
+        // wrong current context is a no-op at best and UB at worst.
+        let _ = self.gl_context.make_current(&self.surface);
+        unsafe {
+            self.gl.delete_framebuffer(self.framebuffer);
+            self.gl.delete_renderbuffer(self.color_renderbuffer);
+        }
+    }
+}
+
+/// Rebuilds `platform_io.Monitors` from winit's monitor list, so ImGui can clamp/position
+/// secondary viewports against the real monitors instead of a single implicit desktop.
+fn update_monitors<EventUserType>(platform_io: &mut ImGuiPlatformIO, target: &EventLoopWindowTarget<EventUserType>) {
+    let mut monitors: Vec<ImGuiPlatformMonitor> = target.available_monitors().map(|monitor| {
+        let pos = monitor.position();
+        let size = monitor.size();
+        let rect = ImVec2 { x: pos.x as f32, y: pos.y as f32 };
+        let extent = ImVec2 { x: size.width as f32, y: size.height as f32 };
+        ImGuiPlatformMonitor {
+            MainPos: rect,
+            MainSize: extent,
+            // winit doesn't expose a separate "work area" (desktop minus taskbars/docks), so use
+            // the full monitor rect for both; still lets ImGui clamp viewports to real monitors.
+            WorkPos: rect,
+            WorkSize: extent,
+            DpiScale: monitor.scale_factor() as f32,
+            PlatformHandle: std::ptr::null_mut(),
+        }
+    }).collect();
+
+    // ImGui expects Monitors[0] to be the primary monitor.
+    if let Some(primary_pos) = target.primary_monitor().map(|m| m.position()) {
+        if let Some(idx) = monitors.iter().position(|m| m.MainPos.x == primary_pos.x as f32 && m.MainPos.y == primary_pos.y as f32) {
+            monitors.swap(0, idx);
+        }
+    }
+
+    if !platform_io.Monitors.Data.is_null() {
+        // SAFETY: this `ImVector` was only ever filled in by a previous call to this function,
+        // using a `Vec` of the same layout.
+        unsafe {
+            drop(Vec::from_raw_parts(platform_io.Monitors.Data, platform_io.Monitors.Size as usize, platform_io.Monitors.Capacity as usize));
+        }
+    }
+
+    let mut monitors = std::mem::ManuallyDrop::new(monitors);
+    platform_io.Monitors.Size = monitors.len() as i32;
+    platform_io.Monitors.Capacity = monitors.capacity() as i32;
+    platform_io.Monitors.Data = monitors.as_mut_ptr();
+}
+
+unsafe extern "C" fn viewport_create_window<EventUserType>(vp: *mut ImGuiViewport) {
+    let vp = &mut *vp;
+    let (target_ptr, main_ptr) = VIEWPORT_CONTEXT.with(Cell::get);
+    if target_ptr.is_null() || main_ptr.is_null() {
+        return;
+    }
+    let target = &*(target_ptr as *const EventLoopWindowTarget<EventUserType>);
+    let main_window = &*(main_ptr as *const MainWindow);
+
+    let window_builder = WindowBuilder::new()
+        .with_position(PhysicalPosition::new(vp.Pos.x as i32, vp.Pos.y as i32))
+        .with_inner_size(PhysicalSize::new(vp.Size.x.max(1.0) as u32, vp.Size.y.max(1.0) as u32))
+        .with_decorations(ImGuiViewportFlags_(vp.Flags as u32) & ImGuiViewportFlags_::ImGuiViewportFlags_NoDecoration == ImGuiViewportFlags_(0))
+        .with_visible(false);
+
+    let window = match glutin_winit::finalize_window(target, window_builder, &main_window.gl_config) {
+        Ok(window) => window,
+        Err(_) => return,
+    };
+
+    let raw_window_handle = window.raw_window_handle();
+    let size = window.inner_size();
+    let attrs = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+        raw_window_handle,
+        NonZeroU32::new(size.width.max(1)).unwrap(),
+        NonZeroU32::new(size.height.max(1)).unwrap(),
+    );
+    let gl_display = main_window.gl_config.display();
+    let surface = match unsafe { gl_display.create_window_surface(&main_window.gl_config, &attrs) } {
+        Ok(surface) => surface,
+        Err(_) => return,
+    };
+
+    let context_attributes = ContextAttributesBuilder::new()
+        .with_sharing(&main_window.gl_context)
+        .build(Some(raw_window_handle));
+    let gl_context = match unsafe { gl_display.create_context(&main_window.gl_config, &context_attributes) } {
+        Ok(gl_context) => match gl_context.make_current(&surface) {
+            Ok(gl_context) => gl_context,
+            Err(_) => return,
+        },
+        Err(_) => return,
+    };
+
+    let window_id = window.id();
+    let viewport_window = Box::new(ViewportWindow { window, surface, gl_context });
+    vp.PlatformUserData = Box::into_raw(viewport_window) as *mut c_void;
+    VIEWPORT_WINDOWS.with(|m| m.borrow_mut().insert(window_id, vp as *mut ImGuiViewport));
+}
+
+unsafe extern "C" fn viewport_destroy_window(vp: *mut ImGuiViewport) {
+    let vp = &mut *vp;
+    if !vp.PlatformUserData.is_null() {
+        let vw = Box::from_raw(vp.PlatformUserData as *mut ViewportWindow);
+        VIEWPORT_WINDOWS.with(|m| m.borrow_mut().remove(&vw.window.id()));
+        drop(vw);
+        vp.PlatformUserData = std::ptr::null_mut();
+    }
+}
+
+unsafe fn viewport_window<'a>(vp: *mut ImGuiViewport) -> Option<&'a ViewportWindow> {
+    let vp = &*vp;
+    (vp.PlatformUserData as *mut ViewportWindow).as_ref()
+}
+
+unsafe extern "C" fn viewport_show_window(vp: *mut ImGuiViewport) {
+    if let Some(vw) = viewport_window(vp) {
+        vw.window.set_visible(true);
+    }
+}
+
+unsafe extern "C" fn viewport_set_window_pos(vp: *mut ImGuiViewport, pos: ImVec2) {
+    if let Some(vw) = viewport_window(vp) {
+        vw.window.set_outer_position(PhysicalPosition::new(pos.x as i32, pos.y as i32));
+    }
+}
+
+unsafe extern "C" fn viewport_get_window_pos(vp: *mut ImGuiViewport, out_pos: *mut ImVec2) {
+    if let Some(vw) = viewport_window(vp) {
+        let pos = vw.window.outer_position().unwrap_or_default();
+        *out_pos = ImVec2 { x: pos.x as f32, y: pos.y as f32 };
+    }
+}
+
+unsafe extern "C" fn viewport_set_window_size(vp: *mut ImGuiViewport, size: ImVec2) {
+    if let Some(vw) = viewport_window(vp) {
+        let _ = vw.window.request_inner_size(PhysicalSize::new(size.x.max(1.0) as u32, size.y.max(1.0) as u32));
+    }
+}
+
+unsafe extern "C" fn viewport_get_window_size(vp: *mut ImGuiViewport, out_size: *mut ImVec2) {
+    if let Some(vw) = viewport_window(vp) {
+        let size = vw.window.inner_size();
+        *out_size = ImVec2 { x: size.width as f32, y: size.height as f32 };
+    }
+}
+
+unsafe extern "C" fn viewport_set_window_focus(vp: *mut ImGuiViewport) {
+    if let Some(vw) = viewport_window(vp) {
+        vw.window.focus_window();
+    }
+}
+
+unsafe extern "C" fn viewport_get_window_focus(vp: *mut ImGuiViewport) -> bool {
+    viewport_window(vp).map_or(false, |vw| vw.window.has_focus())
+}
+
+unsafe extern "C" fn viewport_get_window_minimized(vp: *mut ImGuiViewport) -> bool {
+    viewport_window(vp).map_or(false, |vw| vw.window.is_minimized().unwrap_or(false))
+}
+
+unsafe extern "C" fn viewport_set_window_title(vp: *mut ImGuiViewport, title: *const std::os::raw::c_char) {
+    if let Some(vw) = viewport_window(vp) {
+        let title = std::ffi::CStr::from_ptr(title).to_string_lossy();
+        vw.window.set_title(&title);
+    }
+}
+
+unsafe extern "C" fn viewport_render_window(vp: *mut ImGuiViewport, _render_arg: *mut c_void) {
+    if let Some(vw) = viewport_window(vp) {
+        let _ = vw.gl_context.make_current(&vw.surface);
+    }
+}
+
+unsafe extern "C" fn viewport_swap_buffers(vp: *mut ImGuiViewport, _render_arg: *mut c_void) {
+    if let Some(vw) = viewport_window(vp) {
+        let _ = vw.surface.swap_buffers(&vw.gl_context);
+    }
+}
+
 impl<A: Application> MainWindowWithRenderer<A> {
     pub fn new(main_window: MainWindow, mut renderer: Renderer, app: A) -> MainWindowWithRenderer<A> {
         let size = main_window.window.inner_size();
-        let scale = main_window.window.scale_factor();
+        let scale = main_window.scale_factor();
         let l_size = size.to_logical::<f32>(scale);
         renderer.set_size(l_size.into(), scale as f32);
 
@@ -166,8 +575,58 @@ impl<A: Application> MainWindowWithRenderer<A> {
         self.status.last_input_time = Instant::now();
         self.status.last_input_frame = 0;
     }
-    pub fn do_event_with_data<'ctx, EventUserType>(&'ctx mut self, event: &Event<EventUserType>, control_flow: &mut ControlFlow, data: &'ctx mut A::Data) {
+    /// Sets how winit's scale factor is interpreted for ImGui sizing (see [`HiDpiMode`]) and
+    /// immediately re-applies it to the renderer, so fractional-scale displays stay crisp.
+    pub fn set_hidpi_mode(&mut self, mode: HiDpiMode) {
+        self.main_window.hidpi_mode.set(mode);
+        let scale_factor = self.main_window.scale_factor();
+        let size = self.main_window.window.inner_size();
+        let l_size = self.main_window.to_logical_size::<_, f32>(size);
+        self.renderer.set_size(l_size.into(), scale_factor as f32);
+    }
+    /// Turns on ImGui's multi-viewport mode: dragging a window out of the main viewport spawns
+    /// a real OS window for it, managed by the `Platform_*` callbacks installed here.
+    pub fn enable_viewports<EventUserType>(&mut self, target: &EventLoopWindowTarget<EventUserType>) {
+        unsafe {
+            let io = &mut *ImGui_GetIO();
+            io.ConfigFlags |= ImGuiConfigFlags_::ImGuiConfigFlags_ViewportsEnable.0 as i32;
+            // ImGui drops `ViewportsEnable` unless both backends declare viewport support.
+            io.BackendFlags |= (ImGuiBackendFlags_::ImGuiBackendFlags_PlatformHasViewports.0 | ImGuiBackendFlags_::ImGuiBackendFlags_RendererHasViewports.0) as i32;
+
+            let platform_io = &mut *ImGui_GetPlatformIO();
+            platform_io.Platform_CreateWindow = Some(viewport_create_window::<EventUserType>);
+            platform_io.Platform_DestroyWindow = Some(viewport_destroy_window);
+            platform_io.Platform_ShowWindow = Some(viewport_show_window);
+            platform_io.Platform_SetWindowPos = Some(viewport_set_window_pos);
+            platform_io.Platform_GetWindowPos = Some(viewport_get_window_pos);
+            platform_io.Platform_SetWindowSize = Some(viewport_set_window_size);
+            platform_io.Platform_GetWindowSize = Some(viewport_get_window_size);
+            platform_io.Platform_SetWindowFocus = Some(viewport_set_window_focus);
+            platform_io.Platform_GetWindowFocus = Some(viewport_get_window_focus);
+            platform_io.Platform_GetWindowMinimized = Some(viewport_get_window_minimized);
+            platform_io.Platform_SetWindowTitle = Some(viewport_set_window_title);
+            platform_io.Platform_RenderWindow = Some(viewport_render_window);
+            platform_io.Platform_SwapBuffers = Some(viewport_swap_buffers);
+
+            update_monitors(platform_io, target);
+        }
+    }
+    pub fn do_event_with_data<'ctx, EventUserType>(&'ctx mut self, event: &Event<EventUserType>, control_flow: &mut ControlFlow, target: &EventLoopWindowTarget<EventUserType>, data: &'ctx mut A::Data) {
+        VIEWPORT_CONTEXT.with(|c| c.set((
+            target as *const _ as *const c_void,
+            &self.main_window as *const MainWindow as *const c_void,
+        )));
         match event {
+            Event::Resumed => {
+                unsafe {
+                    let io = &*ImGui_GetIO();
+                    if ImGuiConfigFlags_(io.ConfigFlags as u32) & ImGuiConfigFlags_::ImGuiConfigFlags_ViewportsEnable != ImGuiConfigFlags_(0) {
+                        // Monitors can change while suspended (laptop docked/undocked, display
+                        // hot-plugged), so refresh the list ImGui clamps viewports against.
+                        update_monitors(&mut *ImGui_GetPlatformIO(), target);
+                    }
+                }
+            }
             Event::NewEvents(_) => {
                 let now = Instant::now();
                 unsafe {
@@ -224,13 +683,57 @@ impl<A: Application> MainWindowWithRenderer<A> {
                         data,
                         &mut self.app,
                     );
+
+                    // Follow InputText's cursor with the OS IME candidate window.
+                    let io = &*ImGui_GetIO();
+                    if let Some(ime_data) = io.PlatformImeData.as_ref() {
+                        if ime_data.WantVisible {
+                            let pos = self.main_window.to_physical_pos::<_, i32>(LogicalPosition::new(ime_data.InputPos.x, ime_data.InputPos.y));
+                            let size = self.main_window.to_physical_size::<_, u32>(LogicalSize::new(1.0, ime_data.InputLineHeight));
+                            self.main_window.window.set_ime_cursor_area(pos, size);
+                        }
+                    }
                 }
                 self.main_window.surface.swap_buffers(&self.main_window.gl_context).unwrap();
+
+                unsafe {
+                    let io = &*ImGui_GetIO();
+                    if ImGuiConfigFlags_(io.ConfigFlags as u32) & ImGuiConfigFlags_::ImGuiConfigFlags_ViewportsEnable != ImGuiConfigFlags_(0) {
+                        ImGui_UpdatePlatformWindows();
+                        let platform_io = &*ImGui_GetPlatformIO();
+                        let viewports = std::slice::from_raw_parts(platform_io.Viewports.Data, platform_io.Viewports.Size as usize);
+                        // Viewport 0 is always the main viewport, already drawn above.
+                        for &vp in viewports.iter().skip(1) {
+                            // `Platform_RenderWindow` only activates the viewport's GL context;
+                            // it doesn't know how to submit draw commands, so do that ourselves
+                            // with the same `Renderer` used for the main viewport.
+                            if let Some(render) = platform_io.Platform_RenderWindow {
+                                render(vp, std::ptr::null_mut());
+                            }
+                            let draw_data = (*vp).DrawData;
+                            if !draw_data.is_null() {
+                                self.renderer.render_draw_data(&*draw_data);
+                            }
+                            if let Some(swap) = platform_io.Platform_SwapBuffers {
+                                swap(vp, std::ptr::null_mut());
+                            }
+                        }
+                        // `Platform_RenderWindow` leaves a secondary viewport's context current;
+                        // restore the main one so the next frame's GL calls land on it.
+                        let _ = self.main_window.gl_context.make_current(&self.main_window.surface);
+                    }
+                }
             }
             Event::WindowEvent {
                 window_id,
                 event
-            } if *window_id == self.main_window.window.id() => {
+            } if *window_id != self.main_window.window.id() => {
+                self.handle_viewport_window_event(*window_id, event);
+            }
+            Event::WindowEvent {
+                window_id: _,
+                event
+            } => {
                 use winit::event::WindowEvent::*;
 
                 self.ping_user_input();
@@ -251,7 +754,7 @@ impl<A: Application> MainWindowWithRenderer<A> {
                         }
                     }
                     ScaleFactorChanged { scale_factor, new_inner_size } => {
-                        let scale_factor = *scale_factor as f32;
+                        let scale_factor = self.main_window.hidpi_mode.get().apply(*scale_factor) as f32;
                         unsafe {
                             let io = &mut *ImGui_GetIO();
                             let old_scale_factor = io.DisplayFramebufferScale.x;
@@ -259,6 +762,9 @@ impl<A: Application> MainWindowWithRenderer<A> {
                                 io.MousePos.x *= scale_factor / old_scale_factor;
                                 io.MousePos.y *= scale_factor / old_scale_factor;
                             }
+                            // Scale paddings, roundings, etc. by the same ratio, not the absolute
+                            // factor, so repeated DPI changes don't compound past the real scale.
+                            ImGui_ScaleAllSizes(ImGui_GetStyle(), scale_factor / old_scale_factor);
                         }
                         let new_inner_size = self.main_window.to_logical_size::<_, f32>(**new_inner_size);
                         self.renderer.set_size(new_inner_size.into(), scale_factor);
@@ -309,6 +815,24 @@ impl<A: Application> MainWindowWithRenderer<A> {
                             ImGuiIO_AddInputCharacter(io, *c as u32);
                         }
                     }
+                    Ime(ime) => {
+                        // `Preedit` re-sends the *entire* in-progress composition on every
+                        // keystroke, not a delta, and `AddInputCharactersUTF8` appends committed
+                        // keystrokes rather than replacing a preview. Feeding preedit text through
+                        // it would insert every intermediate partial string as real characters, so
+                        // only the finished `Commit` text is ever pushed; inline preedit display
+                        // would need separate handling, not this path.
+                        if let winit::event::Ime::Commit(text) = ime {
+                            if !text.is_empty() {
+                                if let Ok(text) = std::ffi::CString::new(text.as_str()) {
+                                    unsafe {
+                                        let io = &mut *ImGui_GetIO();
+                                        ImGuiIO_AddInputCharactersUTF8(io, text.as_ptr());
+                                    }
+                                }
+                            }
+                        }
+                    }
                     CursorMoved { position, .. } => {
                         unsafe {
                             let io = &mut *ImGui_GetIO();
@@ -357,12 +881,135 @@ impl<A: Application> MainWindowWithRenderer<A> {
             _ => { }
         }
     }
+
+    /// Routes a `WindowEvent` coming from one of the secondary viewport windows (anything other
+    /// than the main window) into the shared `ImGuiIO` and that viewport's own GL surface.
+    fn handle_viewport_window_event(&mut self, window_id: WindowId, event: &winit::event::WindowEvent) {
+        let vp = match VIEWPORT_WINDOWS.with(|m| m.borrow().get(&window_id).copied()) {
+            Some(vp) => vp,
+            None => return,
+        };
+        self.ping_user_input();
+
+        use winit::event::WindowEvent::*;
+        match event {
+            CloseRequested => {
+                unsafe { (*vp).PlatformRequestClose = true; }
+            }
+            Resized(size) => {
+                if let Some(vw) = unsafe { viewport_window(vp) } {
+                    if let (Some(w), Some(h)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) {
+                        vw.surface.resize(&vw.gl_context, w, h);
+                    }
+                }
+                unsafe { (*vp).PlatformRequestResize = true; }
+            }
+            Moved(_) => {
+                unsafe { (*vp).PlatformRequestMove = true; }
+            }
+            CursorMoved { position, .. } => {
+                if let Some(vw) = unsafe { viewport_window(vp) } {
+                    // ImGui wants multi-viewport mouse positions in the same global/screen space
+                    // it places the viewports themselves in, so offset by this window's origin.
+                    let origin = vw.window.outer_position().unwrap_or_default();
+                    let scale = self.main_window.scale_factor();
+                    let screen_pos = PhysicalPosition::new(origin.x as f64 + position.x, origin.y as f64 + position.y);
+                    let logical: LogicalPosition<f32> = screen_pos.to_logical(scale);
+                    unsafe {
+                        let io = &mut *ImGui_GetIO();
+                        ImGuiIO_AddMousePosEvent(io, logical.x, logical.y);
+                    }
+                }
+            }
+            MouseWheel { delta, phase: winit::event::TouchPhase::Moved, .. } => {
+                let (h, v) = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(h, v) => (*h, *v),
+                    winit::event::MouseScrollDelta::PixelDelta(d) => (d.x as f32, d.y as f32),
+                };
+                unsafe {
+                    let io = &mut *ImGui_GetIO();
+                    ImGuiIO_AddMouseWheelEvent(io, h, v);
+                }
+            }
+            MouseInput { state, button, .. } => {
+                unsafe {
+                    let io = &mut *ImGui_GetIO();
+                    if let Some(btn) = to_imgui_button(*button) {
+                        let pressed = *state == winit::event::ElementState::Pressed;
+                        ImGuiIO_AddMouseButtonEvent(io, btn.bits(), pressed);
+                    }
+                }
+            }
+            CursorLeft { .. } => {
+                unsafe {
+                    let io = &mut *ImGui_GetIO();
+                    ImGuiIO_AddMousePosEvent(io, f32::MAX, f32::MAX);
+                }
+            }
+            KeyboardInput {
+                input: winit::event::KeyboardInput {
+                    virtual_keycode: Some(wkey),
+                    state,
+                    ..
+                },
+                ..
+            } => {
+                if let Some(key) = to_imgui_key(*wkey) {
+                    let pressed = *state == winit::event::ElementState::Pressed;
+                    unsafe {
+                        let io = &mut *ImGui_GetIO();
+                        ImGuiIO_AddKeyEvent(io, ImGuiKey(key.bits()), pressed);
+                    }
+                }
+            }
+            ReceivedCharacter(c) => {
+                unsafe {
+                    let io = &mut *ImGui_GetIO();
+                    ImGuiIO_AddInputCharacter(io, *c as u32);
+                }
+            }
+            Focused(focused) => {
+                unsafe {
+                    let io = &mut *ImGui_GetIO();
+                    ImGuiIO_AddFocusEvent(io, *focused);
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 impl<A: Application<Data=()>> MainWindowWithRenderer<A> {
-    pub fn do_event<'ctx, EventUserType>(&'ctx mut self, event: &Event<EventUserType>, control_flow: &mut ControlFlow) {
+    pub fn do_event<'ctx, EventUserType>(&'ctx mut self, event: &Event<EventUserType>, control_flow: &mut ControlFlow, target: &EventLoopWindowTarget<EventUserType>) {
         static mut DUMMY: () = ();
-        self.do_event_with_data(event, control_flow, unsafe { &mut DUMMY })
+        self.do_event_with_data(event, control_flow, target, unsafe { &mut DUMMY })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Exercises the offscreen GL plumbing (pbuffer surface, FBO, read-back) that `HeadlessWindow`
+    // adds, without going through `Renderer`/`Application`: those types live in `crate::renderer`
+    // and have no public constructor suitable for a unit test, so this clears the FBO to a known
+    // color directly instead of calling `do_frame`.
+    #[test]
+    fn headless_window_renders_and_reads_back_pixels() {
+        let event_loop = winit::event_loop::EventLoopBuilder::<()>::with_user_event()
+            .build();
+        let mut headless = HeadlessWindow::new(&event_loop, 4, 4, 1.0)
+            .expect("create headless window");
+
+        unsafe {
+            headless.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(headless.framebuffer));
+            headless.gl.clear_color(1.0, 0.0, 0.0, 1.0);
+            headless.gl.clear(glow::COLOR_BUFFER_BIT);
+        }
+
+        let image = headless.read_pixels();
+        assert_eq!(image.dimensions(), (4, 4));
+        assert_eq!(image.get_pixel(0, 0), &image::Rgba([255, 0, 0, 255]));
     }
 }
 
@@ -107,4 +107,141 @@ impl<'a> StylePtr<'a> {
     pub fn item_inner_spacing(&self) -> Vector2 {
         self.ptr.ItemInnerSpacing.into()
     }
+    pub fn set_item_spacing(&mut self, item_spacing: Vector2) {
+        self.ptr.ItemSpacing = item_spacing.into();
+    }
+    pub fn set_item_inner_spacing(&mut self, item_inner_spacing: Vector2) {
+        self.ptr.ItemInnerSpacing = item_inner_spacing.into();
+    }
+    pub fn set_frame_padding(&mut self, frame_padding: Vector2) {
+        self.ptr.FramePadding = frame_padding.into();
+    }
+    pub fn set_frame_rounding(&mut self, frame_rounding: f32) {
+        self.ptr.FrameRounding = frame_rounding;
+    }
+    pub fn set_frame_border_size(&mut self, frame_border_size: f32) {
+        self.ptr.FrameBorderSize = frame_border_size;
+    }
+    pub fn window_padding(&self) -> Vector2 {
+        self.ptr.WindowPadding.into()
+    }
+    pub fn set_window_padding(&mut self, window_padding: Vector2) {
+        self.ptr.WindowPadding = window_padding.into();
+    }
+    pub fn window_rounding(&self) -> f32 {
+        self.ptr.WindowRounding
+    }
+    pub fn set_window_rounding(&mut self, window_rounding: f32) {
+        self.ptr.WindowRounding = window_rounding;
+    }
+    pub fn window_border_size(&self) -> f32 {
+        self.ptr.WindowBorderSize
+    }
+    pub fn set_window_border_size(&mut self, window_border_size: f32) {
+        self.ptr.WindowBorderSize = window_border_size;
+    }
+    pub fn window_min_size(&self) -> Vector2 {
+        self.ptr.WindowMinSize.into()
+    }
+    pub fn set_window_min_size(&mut self, window_min_size: Vector2) {
+        self.ptr.WindowMinSize = window_min_size.into();
+    }
+    pub fn window_title_align(&self) -> Vector2 {
+        self.ptr.WindowTitleAlign.into()
+    }
+    pub fn set_window_title_align(&mut self, window_title_align: Vector2) {
+        self.ptr.WindowTitleAlign = window_title_align.into();
+    }
+    pub fn child_rounding(&self) -> f32 {
+        self.ptr.ChildRounding
+    }
+    pub fn set_child_rounding(&mut self, child_rounding: f32) {
+        self.ptr.ChildRounding = child_rounding;
+    }
+    pub fn child_border_size(&self) -> f32 {
+        self.ptr.ChildBorderSize
+    }
+    pub fn set_child_border_size(&mut self, child_border_size: f32) {
+        self.ptr.ChildBorderSize = child_border_size;
+    }
+    pub fn popup_rounding(&self) -> f32 {
+        self.ptr.PopupRounding
+    }
+    pub fn set_popup_rounding(&mut self, popup_rounding: f32) {
+        self.ptr.PopupRounding = popup_rounding;
+    }
+    pub fn popup_border_size(&self) -> f32 {
+        self.ptr.PopupBorderSize
+    }
+    pub fn set_popup_border_size(&mut self, popup_border_size: f32) {
+        self.ptr.PopupBorderSize = popup_border_size;
+    }
+    pub fn scrollbar_size(&self) -> f32 {
+        self.ptr.ScrollbarSize
+    }
+    pub fn set_scrollbar_size(&mut self, scrollbar_size: f32) {
+        self.ptr.ScrollbarSize = scrollbar_size;
+    }
+    pub fn scrollbar_rounding(&self) -> f32 {
+        self.ptr.ScrollbarRounding
+    }
+    pub fn set_scrollbar_rounding(&mut self, scrollbar_rounding: f32) {
+        self.ptr.ScrollbarRounding = scrollbar_rounding;
+    }
+    pub fn grab_min_size(&self) -> f32 {
+        self.ptr.GrabMinSize
+    }
+    pub fn set_grab_min_size(&mut self, grab_min_size: f32) {
+        self.ptr.GrabMinSize = grab_min_size;
+    }
+    pub fn grab_rounding(&self) -> f32 {
+        self.ptr.GrabRounding
+    }
+    pub fn set_grab_rounding(&mut self, grab_rounding: f32) {
+        self.ptr.GrabRounding = grab_rounding;
+    }
+    pub fn tab_rounding(&self) -> f32 {
+        self.ptr.TabRounding
+    }
+    pub fn set_tab_rounding(&mut self, tab_rounding: f32) {
+        self.ptr.TabRounding = tab_rounding;
+    }
+    pub fn indent_spacing(&self) -> f32 {
+        self.ptr.IndentSpacing
+    }
+    pub fn set_indent_spacing(&mut self, indent_spacing: f32) {
+        self.ptr.IndentSpacing = indent_spacing;
+    }
+    pub fn cell_padding(&self) -> Vector2 {
+        self.ptr.CellPadding.into()
+    }
+    pub fn set_cell_padding(&mut self, cell_padding: Vector2) {
+        self.ptr.CellPadding = cell_padding.into();
+    }
+    pub fn disabled_alpha(&self) -> f32 {
+        self.ptr.DisabledAlpha
+    }
+    pub fn set_disabled_alpha(&mut self, disabled_alpha: f32) {
+        self.ptr.DisabledAlpha = disabled_alpha;
+    }
+    pub fn button_text_align(&self) -> Vector2 {
+        self.ptr.ButtonTextAlign.into()
+    }
+    pub fn set_button_text_align(&mut self, button_text_align: Vector2) {
+        self.ptr.ButtonTextAlign = button_text_align.into();
+    }
+    pub fn selectable_text_align(&self) -> Vector2 {
+        self.ptr.SelectableTextAlign.into()
+    }
+    pub fn set_selectable_text_align(&mut self, selectable_text_align: Vector2) {
+        self.ptr.SelectableTextAlign = selectable_text_align.into();
+    }
+    /// Scales the style's paddings, spacings, roundings and minimum sizes by `factor`, through
+    /// ImGui's `ScaleAllSizes`. Border sizes and colors are not changed. Useful to call after a
+    /// monitor DPI change.
+    pub fn scale_all_sizes(&mut self, factor: f32) {
+        unsafe {
+            ImGui_ScaleAllSizes(self.ptr, factor);
+        }
+    }
 }